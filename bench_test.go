@@ -0,0 +1,129 @@
+package main
+
+import (
+	"encoding/binary"
+	"testing"
+)
+
+// number of keys in the tries built by the benchmarks
+const benchSize = 10000
+
+// sequentialKeys returns the big endian encoding of 0 to n-1,
+// which are sorted and share long prefixes.
+func sequentialKeys(n int) [][]byte {
+	keys := make([][]byte, n)
+	for i := range keys {
+		key := make([]byte, 8)
+		binary.BigEndian.PutUint64(key, uint64(i))
+		keys[i] = key
+	}
+	return keys
+}
+
+// hashedKeys returns 32 bytes hashed keys, like the keys of Ethereum's state trie,
+// which are randomly distributed.
+func hashedKeys(n int) [][]byte {
+	keys := sequentialKeys(n)
+	for i, key := range keys {
+		keys[i] = Keccak256(key)
+	}
+	return keys
+}
+
+func benchValues(keys [][]byte) [][]byte {
+	values := make([][]byte, len(keys))
+	for i, key := range keys {
+		values[i] = Keccak256(key, []byte("value"))
+	}
+	return values
+}
+
+func buildTrie(keys [][]byte, values [][]byte) *Trie {
+	tr := NewTrie()
+	for i, key := range keys {
+		tr.Put(key, values[i])
+	}
+	return tr
+}
+
+func BenchmarkPutSequential(b *testing.B) {
+	keys := sequentialKeys(benchSize)
+	values := benchValues(keys)
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		buildTrie(keys, values)
+	}
+}
+
+func BenchmarkPutRandom(b *testing.B) {
+	keys := hashedKeys(benchSize)
+	values := benchValues(keys)
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		buildTrie(keys, values)
+	}
+}
+
+func BenchmarkGet(b *testing.B) {
+	// only the first half of the keys exist, so that half of the reads are misses
+	keys := hashedKeys(2 * benchSize)
+	tr := buildTrie(keys[:benchSize], benchValues(keys[:benchSize]))
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		tr.Get(keys[i%len(keys)])
+	}
+}
+
+func BenchmarkDelete(b *testing.B) {
+	keys := hashedKeys(benchSize)
+	values := benchValues(keys)
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		b.StopTimer()
+		tr := buildTrie(keys, values)
+		b.StartTimer()
+		for _, key := range keys {
+			tr.Delete(key)
+		}
+	}
+}
+
+// the root hash is computed from scratch on every call
+func BenchmarkHash(b *testing.B) {
+	keys := hashedKeys(benchSize)
+	tr := buildTrie(keys, benchValues(keys))
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		tr.Hash()
+	}
+}
+
+func BenchmarkProve(b *testing.B) {
+	keys := hashedKeys(benchSize)
+	tr := buildTrie(keys, benchValues(keys))
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		tr.Prove(keys[i%len(keys)])
+	}
+}
+
+func BenchmarkVerifyProof(b *testing.B) {
+	keys := hashedKeys(benchSize)
+	tr := buildTrie(keys, benchValues(keys))
+	rootHash := tr.Hash()
+
+	proved := keys[:100]
+	proofs := make([]Proof, len(proved))
+	for i, key := range proved {
+		proofs[i], _ = tr.Prove(key)
+	}
+
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		j := i % len(proved)
+		_, err := VerifyProof(rootHash, proved[j], proofs[j])
+		if err != nil {
+			b.Fatal(err)
+		}
+	}
+}
@@ -19,6 +19,16 @@ func FromNibbleByte(n byte) (Nibble, error) {
 	return Nibble(n), nil
 }
 
+// isNibblePath returns whether every nibble in the path is less than 16
+func isNibblePath(ns []Nibble) bool {
+	for _, n := range ns {
+		if !IsNibble(byte(n)) {
+			return false
+		}
+	}
+	return true
+}
+
 // nibbles contain one nibble per byte
 func FromNibbleBytes(nibbles []byte) ([]Nibble, error) {
 	ns := make([]Nibble, 0, len(nibbles))
@@ -39,6 +49,10 @@ func FromByte(b byte) []Nibble {
 	}
 }
 
+// FromBytes splits each byte into two nibbles, high nibble first,
+// so the result always has an even length.
+// [1, 100] => [0, 1, 6, 4]
+// [] => []
 func FromBytes(bs []byte) []Nibble {
 	ns := make([]Nibble, 0, len(bs)*2)
 	for _, b := range bs {
@@ -108,3 +122,13 @@ func PrefixMatchedLen(node1 []Nibble, node2 []Nibble) int {
 
 	return matched
 }
+
+// HasPrefix reports whether ns starts with prefix.
+// An empty prefix is a prefix of everything.
+// [0,1,2,3], [0,1,2] => true
+// [0,1,2,3], [0,1,2,3] => true
+// [0,1,2], [0,1,2,3] => false
+// [0,1,2,3], [0,1,3] => false
+func HasPrefix(ns []Nibble, prefix []Nibble) bool {
+	return PrefixMatchedLen(ns, prefix) == len(prefix)
+}
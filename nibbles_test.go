@@ -76,6 +76,10 @@ func TestToPrefixed(t *testing.T) {
 func TestFromBytes(t *testing.T) {
 	// [1, 100] -> ['0x01', '0x64']
 	require.Equal(t, []Nibble{0, 1, 6, 4}, FromBytes([]byte{1, 100}))
+	// high nibble first
+	require.Equal(t, []Nibble{10, 11}, FromBytes([]byte{0xab}))
+	require.Equal(t, []Nibble{}, FromBytes([]byte{}))
+	require.Equal(t, []Nibble{}, FromBytes(nil))
 }
 
 func TestToBytes(t *testing.T) {
@@ -88,3 +92,26 @@ func TestPrefixMatchedLen(t *testing.T) {
 	require.Equal(t, 4, PrefixMatchedLen([]Nibble{0, 1, 2, 3}, []Nibble{0, 1, 2, 3}))
 	require.Equal(t, 4, PrefixMatchedLen([]Nibble{0, 1, 2, 3}, []Nibble{0, 1, 2, 3, 4}))
 }
+
+func TestHasPrefix(t *testing.T) {
+	cases := []struct {
+		ns       []Nibble
+		prefix   []Nibble
+		expected bool
+	}{
+		{[]Nibble{}, []Nibble{}, true},
+		{[]Nibble{0, 1}, []Nibble{}, true},
+		{[]Nibble{}, []Nibble{0}, false},
+		{[]Nibble{0, 1, 2, 3}, []Nibble{0, 1, 2, 3}, true},
+		{[]Nibble{0, 1, 2, 3}, []Nibble{0, 1, 2}, true},
+		{[]Nibble{0, 1, 2}, []Nibble{0, 1, 2, 3}, false},
+		{[]Nibble{0, 1, 2, 3}, []Nibble{0, 1, 2, 4}, false},
+		{[]Nibble{0, 1, 2, 3}, []Nibble{1}, false},
+		{[]Nibble{5}, []Nibble{5}, true},
+		{[]Nibble{5}, []Nibble{6}, false},
+	}
+
+	for _, c := range cases {
+		require.Equal(t, c.expected, HasPrefix(c.ns, c.prefix), "%v %v", c.ns, c.prefix)
+	}
+}
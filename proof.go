@@ -1,9 +1,12 @@
 package main
 
 import (
+	"bytes"
 	"fmt"
+	"sort"
 
 	"github.com/ethereum/go-ethereum/common"
+	"github.com/ethereum/go-ethereum/rlp"
 	"github.com/ethereum/go-ethereum/trie"
 )
 
@@ -31,10 +34,47 @@ func NewProofDB() *ProofDB {
 	}
 }
 
+// NewProofDBFromBytes decodes a proof encoded by Serialize.
+// Each node is stored under its own hash, so a tampered node can't take the place
+// of the node its parent refers to.
+func NewProofDBFromBytes(data []byte) (*ProofDB, error) {
+	var nodes [][]byte
+	err := rlp.DecodeBytes(data, &nodes)
+	if err != nil {
+		return nil, fmt.Errorf("could not decode proof: %w", err)
+	}
+
+	proof := NewProofDB()
+	for _, node := range nodes {
+		proof.Put(Keccak256(node), node)
+	}
+	return proof, nil
+}
+
+// Serialize encodes the proof nodes as a RLP list sorted by their hashes,
+// so that a proof can be sent as bytes, and is always encoded the same.
+func (w *ProofDB) Serialize() []byte {
+	keys := make([]string, 0, len(w.kv))
+	for key := range w.kv {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+
+	nodes := make([][]byte, 0, len(keys))
+	for _, key := range keys {
+		nodes = append(nodes, w.kv[key])
+	}
+
+	rlp, err := rlp.EncodeToBytes(nodes)
+	if err != nil {
+		panic(err)
+	}
+	return rlp
+}
+
 func (w *ProofDB) Put(key []byte, value []byte) error {
 	keyS := fmt.Sprintf("%x", key)
 	w.kv[keyS] = value
-	fmt.Printf("put key: %x, value: %x\n", key, value)
 	return nil
 }
 
@@ -58,31 +98,43 @@ func (w *ProofDB) Get(key []byte) ([]byte, error) {
 	return val, nil
 }
 
-// Prove returns the merkle proof for the given key, which is
+// Prove returns the merkle proof for the given key.
+// Use GetWithProof for a *ProofDB that can be sent as bytes with Serialize.
 func (t *Trie) Prove(key []byte) (Proof, bool) {
+	_, proof, found := t.GetWithProof(key)
+	if !found {
+		return nil, false
+	}
+	return proof, true
+}
+
+// GetWithProof returns the value for the given key and its merkle proof, collected
+// in a single traversal, so that the value and proof are always consistent.
+// If the key does not exist, the proof proves its absence.
+func (t *Trie) GetWithProof(key []byte) ([]byte, *ProofDB, bool) {
 	proof := NewProofDB()
 	node := t.root
 	nibbles := FromBytes(key)
 
 	for {
-		proof.Put(Hash(node), Serialize(node))
-
 		if IsEmptyNode(node) {
-			return nil, false
+			return nil, proof, false
 		}
 
+		proof.Put(Hash(node), Serialize(node))
+
 		if leaf, ok := node.(*LeafNode); ok {
 			matched := PrefixMatchedLen(leaf.Path, nibbles)
 			if matched != len(leaf.Path) || matched != len(nibbles) {
-				return nil, false
+				return nil, proof, false
 			}
 
-			return proof, true
+			return leaf.Value, proof, true
 		}
 
 		if branch, ok := node.(*BranchNode); ok {
 			if len(nibbles) == 0 {
-				return proof, branch.HasValue()
+				return branch.Value, proof, branch.HasValue()
 			}
 
 			b, remaining := nibbles[0], nibbles[1:]
@@ -92,14 +144,13 @@ func (t *Trie) Prove(key []byte) (Proof, bool) {
 		}
 
 		if ext, ok := node.(*ExtensionNode); ok {
-			matched := PrefixMatchedLen(ext.Path, nibbles)
 			// E 01020304
 			//   010203
-			if matched < len(ext.Path) {
-				return nil, false
+			if !HasPrefix(nibbles, ext.Path) {
+				return nil, proof, false
 			}
 
-			nibbles = nibbles[matched:]
+			nibbles = nibbles[len(ext.Path):]
 			node = ext.Next
 			continue
 		}
@@ -110,6 +161,10 @@ func (t *Trie) Prove(key []byte) (Proof, bool) {
 
 // VerifyProof verify the proof for the given key under the given root hash using go-ethereum's VerifyProof implementation.
 // It returns the value for the key if the proof is valid, otherwise error will be returned
+// Since no key exists in an empty trie, no proof node is needed under the empty root hash.
 func VerifyProof(rootHash []byte, key []byte, proof Proof) (value []byte, err error) {
+	if bytes.Equal(rootHash, EmptyNodeHash) {
+		return nil, nil
+	}
 	return trie.VerifyProof(common.BytesToHash(rootHash), key, proof)
 }
@@ -1,9 +1,13 @@
 package main
 
 import (
+	"bytes"
 	"fmt"
+	"math/big"
 	"testing"
 
+	"github.com/ethereum/go-ethereum/common"
+	"github.com/ethereum/go-ethereum/rlp"
 	"github.com/ethereum/go-ethereum/trie"
 	"github.com/stretchr/testify/require"
 )
@@ -82,3 +86,210 @@ func TestProveAndVerifyProof(t *testing.T) {
 		require.Error(t, err)
 	})
 }
+
+func TestGetWithProof(t *testing.T) {
+	tr := NewTrie()
+	tr.Put([]byte{1, 2, 3}, []byte("hello"))
+	tr.Put([]byte{1, 2, 3, 4, 5}, []byte("world"))
+	tr.Put([]byte{1, 3}, []byte("trie"))
+	rootHash := tr.Hash()
+
+	t.Run("should return the value and a proof that can be verified for an existing key", func(t *testing.T) {
+		for _, key := range [][]byte{{1, 2, 3}, {1, 2, 3, 4, 5}, {1, 3}} {
+			val, proof, found := tr.GetWithProof(key)
+			require.True(t, found)
+
+			expected, _ := tr.Get(key)
+			require.Equal(t, expected, val)
+
+			verified, err := VerifyProof(rootHash, key, proof)
+			require.NoError(t, err)
+			require.Equal(t, val, verified)
+		}
+	})
+
+	t.Run("should return a proof of absence for a non-exist key", func(t *testing.T) {
+		notExistKeys := [][]byte{
+			{1, 2, 3, 4},    // diverges from a leaf node
+			{1, 2, 3, 0x50}, // ends at an empty branch slot
+			{1, 2, 4},       // diverges from an extension node
+			{1, 2},          // ends within an extension node
+			{5},             // diverges from the root node
+		}
+		for _, key := range notExistKeys {
+			val, proof, found := tr.GetWithProof(key)
+			require.False(t, found, "key: %x", key)
+			require.Nil(t, val)
+
+			verified, err := VerifyProof(rootHash, key, proof)
+			require.NoError(t, err, "key: %x", key)
+			require.Nil(t, verified)
+		}
+	})
+
+	t.Run("should not find any key in an empty trie", func(t *testing.T) {
+		val, _, found := NewTrie().GetWithProof([]byte{1, 2, 3})
+		require.False(t, found)
+		require.Nil(t, val)
+	})
+}
+
+func TestDegenerateProofs(t *testing.T) {
+	t.Run("should prove the absence of any key in an empty trie with no proof node", func(t *testing.T) {
+		tr := NewTrie()
+		_, proof, found := tr.GetWithProof([]byte{1, 2, 3})
+		require.False(t, found)
+		require.Len(t, proof.kv, 0)
+
+		val, err := VerifyProof(tr.Hash(), []byte{1, 2, 3}, proof)
+		require.NoError(t, err)
+		require.Nil(t, val)
+	})
+
+	t.Run("should reject a proof with no proof node for a non-empty trie", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3}, []byte("hello"))
+
+		_, err := VerifyProof(tr.Hash(), []byte{1, 2, 3}, NewProofDB())
+		require.Error(t, err)
+	})
+
+	t.Run("should prove the only key of a trie with just the leaf node", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3}, []byte("hello"))
+
+		_, proof, found := tr.GetWithProof([]byte{1, 2, 3})
+		require.True(t, found)
+		require.Len(t, proof.kv, 1)
+
+		val, err := VerifyProof(tr.Hash(), []byte{1, 2, 3}, proof)
+		require.NoError(t, err)
+		require.Equal(t, []byte("hello"), val)
+	})
+
+	t.Run("should prove the absence of other keys in a trie with a single leaf node", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3}, []byte("hello"))
+
+		_, proof, found := tr.GetWithProof([]byte{1, 2, 4})
+		require.False(t, found)
+
+		val, err := VerifyProof(tr.Hash(), []byte{1, 2, 4}, proof)
+		require.NoError(t, err)
+		require.Nil(t, val)
+	})
+}
+
+func TestProofSerialize(t *testing.T) {
+	tr := NewTrie()
+	tr.Put([]byte{1, 2, 3}, []byte("hello"))
+	tr.Put([]byte{1, 2, 3, 4, 5}, []byte("world"))
+	tr.Put([]byte{1, 3}, []byte("trie"))
+
+	t.Run("should verify a proof decoded from its serialized bytes", func(t *testing.T) {
+		key := []byte{1, 2, 3, 4, 5}
+		_, proof, found := tr.GetWithProof(key)
+		require.True(t, found)
+
+		decoded, err := NewProofDBFromBytes(proof.Serialize())
+		require.NoError(t, err)
+		require.Equal(t, proof, decoded)
+
+		val, err := VerifyProof(tr.Hash(), key, decoded)
+		require.NoError(t, err)
+		require.Equal(t, []byte("world"), val)
+	})
+
+	t.Run("should always serialize a proof to the same bytes", func(t *testing.T) {
+		_, proof1, _ := tr.GetWithProof([]byte{1, 2, 3, 4, 5})
+		_, proof2, _ := tr.GetWithProof([]byte{1, 2, 3, 4, 5})
+		require.Equal(t, proof1.Serialize(), proof2.Serialize())
+	})
+
+	t.Run("should fail to decode malformed bytes", func(t *testing.T) {
+		_, proof, _ := tr.GetWithProof([]byte{1, 2, 3, 4, 5})
+		data := proof.Serialize()
+
+		_, err := NewProofDBFromBytes(data[:len(data)-1])
+		require.Error(t, err)
+	})
+}
+
+// A light client only knows the state root from a block header, it gets an account
+// and its proof from an untrusted full node as bytes, and verifies it with the root.
+func TestLightClient(t *testing.T) {
+	type account struct {
+		Nonce   uint64
+		Balance *big.Int
+	}
+
+	addresses := []common.Address{
+		common.HexToAddress("0x1000000000000000000000000000000000000001"),
+		common.HexToAddress("0x2000000000000000000000000000000000000002"),
+		common.HexToAddress("0x3000000000000000000000000000000000000003"),
+	}
+
+	// the full node keeps all accounts, keyed by the hash of the address like Ethereum's state trie
+	fullNode := NewTrie()
+	for i, address := range addresses {
+		acc, err := rlp.EncodeToBytes(account{
+			Nonce:   uint64(i),
+			Balance: big.NewInt(int64(1000 * (i + 1))),
+		})
+		require.NoError(t, err)
+		fullNode.Put(Keccak256(address.Bytes()), acc)
+	}
+
+	// the root hash from the block header
+	stateRoot := fullNode.Hash()
+
+	// the full node sends the proof for the 2nd account over the network
+	key := Keccak256(addresses[1].Bytes())
+	_, proof, found := fullNode.GetWithProof(key)
+	require.True(t, found)
+	proofBytes := proof.Serialize()
+
+	t.Run("should verify the balance with the state root", func(t *testing.T) {
+		received, err := NewProofDBFromBytes(proofBytes)
+		require.NoError(t, err)
+
+		val, err := VerifyProof(stateRoot, key, received)
+		require.NoError(t, err)
+
+		var acc account
+		require.NoError(t, rlp.DecodeBytes(val, &acc))
+		require.Equal(t, uint64(1), acc.Nonce)
+		require.Equal(t, big.NewInt(2000), acc.Balance)
+	})
+
+	t.Run("should fail the verification if the account in the proof was tampered", func(t *testing.T) {
+		val, _ := fullNode.Get(key)
+		tampered := make([]byte, len(proofBytes))
+		copy(tampered, proofBytes)
+
+		// change the balance in the leaf node
+		i := bytes.Index(tampered, val)
+		require.True(t, i >= 0)
+		tampered[i+len(val)-1] ^= 1
+
+		received, err := NewProofDBFromBytes(tampered)
+		require.NoError(t, err)
+
+		_, err = VerifyProof(stateRoot, key, received)
+		require.Error(t, err)
+	})
+
+	t.Run("should fail the verification if the proof is malformed", func(t *testing.T) {
+		_, err := NewProofDBFromBytes(proofBytes[1:])
+		require.Error(t, err)
+	})
+
+	t.Run("should fail the verification with a different state root", func(t *testing.T) {
+		received, err := NewProofDBFromBytes(proofBytes)
+		require.NoError(t, err)
+
+		otherRoot := Keccak256([]byte("another block"))
+		_, err = VerifyProof(otherRoot, key, received)
+		require.Error(t, err)
+	})
+}
@@ -1,5 +1,7 @@
 package main
 
+import "fmt"
+
 type Trie struct {
 	root Node
 }
@@ -16,8 +18,20 @@ func (t *Trie) Hash() []byte {
 }
 
 func (t *Trie) Get(key []byte) ([]byte, bool) {
+	return t.GetAtPath(FromBytes(key))
+}
+
+// GetAtPath returns the value at the given nibble path.
+// Unlike byte keys, a path can have an odd number of nibbles.
+// A path containing a nibble not less than 16 is never found.
+// Prefer Get, unless the caller already works with nibbles.
+func (t *Trie) GetAtPath(path []Nibble) ([]byte, bool) {
+	if !isNibblePath(path) {
+		return nil, false
+	}
+
 	node := t.root
-	nibbles := FromBytes(key)
+	nibbles := path
 	for {
 		if IsEmptyNode(node) {
 			return nil, false
@@ -43,14 +57,13 @@ func (t *Trie) Get(key []byte) ([]byte, bool) {
 		}
 
 		if ext, ok := node.(*ExtensionNode); ok {
-			matched := PrefixMatchedLen(ext.Path, nibbles)
 			// E 01020304
 			//   010203
-			if matched < len(ext.Path) {
+			if !HasPrefix(nibbles, ext.Path) {
 				return nil, false
 			}
 
-			nibbles = nibbles[matched:]
+			nibbles = nibbles[len(ext.Path):]
 			node = ext.Next
 			continue
 		}
@@ -64,16 +77,38 @@ func (t *Trie) Get(key []byte) ([]byte, bool) {
 // - When stopped at an EmptyNode, replace it with a new LeafNode with the remaining path.
 // - When stopped at a LeafNode, convert it to an ExtensionNode and add a new branch and a new LeafNode.
 // - When stopped at an ExtensionNode, convert it to another ExtensionNode with shorter path and create a new BranchNode points to the ExtensionNode.
+// Putting an empty value deletes the key, as Ethereum does, so an empty value and an absent key
+// are the same thing. Empty values are never stored, since a BranchNode encodes an empty value
+// the same as no value, and the root hash couldn't commit to it.
 func (t *Trie) Put(key []byte, value []byte) {
+	// the nibbles of a byte key are always valid, so there is no error to handle
+	t.PutAtPath(FromBytes(key), value)
+}
+
+// PutAtPath adds a value at the given nibble path, which can have an odd number of nibbles.
+// It returns an error, and leaves the trie unchanged, if the path contains a nibble
+// not less than 16.
+// Prefer Put, unless the caller already works with nibbles.
+func (t *Trie) PutAtPath(path []Nibble, value []byte) error {
+	if !isNibblePath(path) {
+		return fmt.Errorf("path contains non-nibble: %v", path)
+	}
+
+	if len(value) == 0 {
+		t.DeleteAtPath(path)
+		return nil
+	}
+
 	// need to use pointer, so that I can update root in place without
 	// keeping trace of the parent node
 	node := &t.root
-	nibbles := FromBytes(key)
+	// copy the path, since nodes keep slices of it
+	nibbles := append([]Nibble{}, path...)
 	for {
 		if IsEmptyNode(*node) {
 			leaf := NewLeafNodeFromNibbles(nibbles, value)
 			*node = leaf
-			return
+			return nil
 		}
 
 		if leaf, ok := (*node).(*LeafNode); ok {
@@ -83,7 +118,7 @@ func (t *Trie) Put(key []byte, value []byte) {
 			if matched == len(nibbles) && matched == len(leaf.Path) {
 				newLeaf := NewLeafNodeFromNibbles(leaf.Path, value)
 				*node = newLeaf
-				return
+				return nil
 			}
 
 			branch := NewBranchNode()
@@ -129,13 +164,13 @@ func (t *Trie) Put(key []byte, value []byte) {
 				branch.SetBranch(branchNibble, newLeaf)
 			}
 
-			return
+			return nil
 		}
 
 		if branch, ok := (*node).(*BranchNode); ok {
 			if len(nibbles) == 0 {
 				branch.SetValue(value)
-				return
+				return nil
 			}
 
 			b, remaining := nibbles[0], nibbles[1:]
@@ -154,7 +189,6 @@ func (t *Trie) Put(key []byte, value []byte) {
 				// E 01020304
 				// + 010203 good
 				extNibbles, branchNibble, extRemainingnibbles := ext.Path[:matched], ext.Path[matched], ext.Path[matched+1:]
-				nodeBranchNibble, nodeLeafNibbles := nibbles[matched], nibbles[matched+1:]
 				branch := NewBranchNode()
 				if len(extRemainingnibbles) == 0 {
 					// E 0102030
@@ -167,8 +201,16 @@ func (t *Trie) Put(key []byte, value []byte) {
 					branch.SetBranch(branchNibble, newExt)
 				}
 
-				remainingLeaf := NewLeafNodeFromNibbles(nodeLeafNibbles, value)
-				branch.SetBranch(nodeBranchNibble, remainingLeaf)
+				if matched == len(nibbles) {
+					// the key ends within the extension path, the value goes to the new branch
+					// E 01020304
+					// + 0102 good
+					branch.SetValue(value)
+				} else {
+					nodeBranchNibble, nodeLeafNibbles := nibbles[matched], nibbles[matched+1:]
+					remainingLeaf := NewLeafNodeFromNibbles(nodeLeafNibbles, value)
+					branch.SetBranch(nodeBranchNibble, remainingLeaf)
+				}
 
 				// if there is no shared extension nibbles any more, then we don't need the extension node
 				// any more
@@ -180,7 +222,7 @@ func (t *Trie) Put(key []byte, value []byte) {
 					// otherwise create a new extension node
 					*node = NewExtensionNode(extNibbles, branch)
 				}
-				return
+				return nil
 			}
 
 			nibbles = nibbles[matched:]
@@ -192,3 +234,224 @@ func (t *Trie) Put(key []byte, value []byte) {
 	}
 
 }
+
+// Delete removes the key from the trie, returns false if the key does not exist.
+// After the value is removed, the nodes along the path are collapsed, so that the trie
+// has the same hash as if the key had never been put:
+// - A BranchNode with a single child and no value is merged with the child.
+// - A BranchNode with no child becomes a LeafNode holding its value.
+// - An ExtensionNode followed by a LeafNode or another ExtensionNode is merged with it.
+func (t *Trie) Delete(key []byte) bool {
+	return t.DeleteAtPath(FromBytes(key))
+}
+
+// DeleteAtPath removes the value at the given nibble path, which can have an odd number of nibbles.
+// A path containing a nibble not less than 16 is never found, so nothing is removed.
+// Prefer Delete, unless the caller already works with nibbles.
+func (t *Trie) DeleteAtPath(path []Nibble) bool {
+	if !isNibblePath(path) {
+		return false
+	}
+
+	root, deleted := deleteNode(t.root, path)
+	if deleted {
+		t.root = root
+	}
+	return deleted
+}
+
+// ClearPrefix removes all the keys that start with the given prefix, and returns the
+// number of keys removed.
+// The whole subtree under the prefix is detached at once, instead of deleting
+// keys one by one.
+func (t *Trie) ClearPrefix(prefix []byte) int {
+	root, removed := clearPrefix(t.root, FromBytes(prefix))
+	if removed > 0 {
+		t.root = root
+	}
+	return removed
+}
+
+// deleteNode removes the value at the given path from the node, and returns
+// the node to replace it with.
+func deleteNode(node Node, nibbles []Nibble) (Node, bool) {
+	if IsEmptyNode(node) {
+		return nil, false
+	}
+
+	if leaf, ok := node.(*LeafNode); ok {
+		matched := PrefixMatchedLen(leaf.Path, nibbles)
+		if matched != len(leaf.Path) || matched != len(nibbles) {
+			return leaf, false
+		}
+		return nil, true
+	}
+
+	if branch, ok := node.(*BranchNode); ok {
+		if len(nibbles) == 0 {
+			if !branch.HasValue() {
+				return branch, false
+			}
+			branch.RemoveValue()
+			return collapseBranch(branch), true
+		}
+
+		b, remaining := nibbles[0], nibbles[1:]
+		child, deleted := deleteNode(branch.Branches[b], remaining)
+		if !deleted {
+			return branch, false
+		}
+		branch.SetBranch(b, child)
+		return collapseBranch(branch), true
+	}
+
+	if ext, ok := node.(*ExtensionNode); ok {
+		if !HasPrefix(nibbles, ext.Path) {
+			return ext, false
+		}
+
+		next, deleted := deleteNode(ext.Next, nibbles[len(ext.Path):])
+		if !deleted {
+			return ext, false
+		}
+		return collapseExtension(ext.Path, next), true
+	}
+
+	panic("unknown type")
+}
+
+// clearPrefix removes the subtree at the given path from the node, and returns
+// the node to replace it with, and the number of removed values.
+func clearPrefix(node Node, nibbles []Nibble) (Node, int) {
+	if IsEmptyNode(node) {
+		return nil, 0
+	}
+
+	// the node itself is under the prefix
+	if len(nibbles) == 0 {
+		return nil, countValues(node)
+	}
+
+	if leaf, ok := node.(*LeafNode); ok {
+		if !HasPrefix(leaf.Path, nibbles) {
+			return leaf, 0
+		}
+		return nil, 1
+	}
+
+	if branch, ok := node.(*BranchNode); ok {
+		b, remaining := nibbles[0], nibbles[1:]
+		child, removed := clearPrefix(branch.Branches[b], remaining)
+		if removed == 0 {
+			return branch, 0
+		}
+		branch.SetBranch(b, child)
+		return collapseBranch(branch), removed
+	}
+
+	if ext, ok := node.(*ExtensionNode); ok {
+		// the prefix ends within the extension's path
+		// E 01020304
+		// - 0102
+		if HasPrefix(ext.Path, nibbles) {
+			return nil, countValues(ext.Next)
+		}
+
+		if !HasPrefix(nibbles, ext.Path) {
+			return ext, 0
+		}
+
+		next, removed := clearPrefix(ext.Next, nibbles[len(ext.Path):])
+		if removed == 0 {
+			return ext, 0
+		}
+		return collapseExtension(ext.Path, next), removed
+	}
+
+	panic("unknown type")
+}
+
+// collapseBranch returns the node to replace a branch node with, after one of
+// its children or its value was removed.
+func collapseBranch(branch *BranchNode) Node {
+	children := 0
+	last := 0
+	for i, child := range branch.Branches {
+		if !IsEmptyNode(child) {
+			children++
+			last = i
+		}
+	}
+
+	if children == 0 {
+		if !branch.HasValue() {
+			return nil
+		}
+		// B hello
+		// => L hello
+		return NewLeafNodeFromNibbles([]Nibble{}, branch.Value)
+	}
+
+	if children > 1 || branch.HasValue() {
+		return branch
+	}
+
+	// only one child left, merge it into a node with the child's nibble
+	// B 0 => L 506 coin
+	// => L 0506 coin
+	return collapseExtension([]Nibble{Nibble(last)}, branch.Branches[last])
+}
+
+// collapseExtension returns the node to replace an extension node with the given
+// path and next node, merging the path into the next node when possible.
+func collapseExtension(path []Nibble, next Node) Node {
+	if IsEmptyNode(next) {
+		return nil
+	}
+
+	if leaf, ok := next.(*LeafNode); ok {
+		return NewLeafNodeFromNibbles(concatNibbles(path, leaf.Path), leaf.Value)
+	}
+
+	if ext, ok := next.(*ExtensionNode); ok {
+		return NewExtensionNode(concatNibbles(path, ext.Path), ext.Next)
+	}
+
+	return NewExtensionNode(path, next)
+}
+
+// countValues returns the number of values stored under the node.
+func countValues(node Node) int {
+	if IsEmptyNode(node) {
+		return 0
+	}
+
+	if _, ok := node.(*LeafNode); ok {
+		return 1
+	}
+
+	if branch, ok := node.(*BranchNode); ok {
+		count := 0
+		if branch.HasValue() {
+			count++
+		}
+		for _, child := range branch.Branches {
+			count += countValues(child)
+		}
+		return count
+	}
+
+	if ext, ok := node.(*ExtensionNode); ok {
+		return countValues(ext.Next)
+	}
+
+	panic("unknown type")
+}
+
+// concatNibbles returns a new slice, so that the result doesn't share
+// the underlying array with either of the inputs.
+func concatNibbles(a []Nibble, b []Nibble) []Nibble {
+	ns := make([]Nibble, 0, len(a)+len(b))
+	ns = append(ns, a...)
+	return append(ns, b...)
+}
@@ -4,6 +4,7 @@ import (
 	"fmt"
 	"testing"
 
+	"github.com/ethereum/go-ethereum/trie"
 	"github.com/stretchr/testify/require"
 )
 
@@ -157,3 +158,512 @@ func TestPutOrder(t *testing.T) {
 
 	require.Equal(t, trie1.Hash(), trie2.Hash())
 }
+
+func TestDelete(t *testing.T) {
+	// keys are put in this order, so that no key is put after a longer key sharing its prefix
+	keys := [][]byte{
+		{1, 2},
+		{1, 2, 3},
+		{1, 2, 3, 4},
+		{1, 2, 3, 4, 5, 6},
+		{1, 3},
+		{5, 6, 7},
+	}
+
+	t.Run("should return false if key does not exist", func(t *testing.T) {
+		tr := NewTrie()
+		require.False(t, tr.Delete([]byte{1, 2, 3, 4}))
+
+		tr.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		hash := tr.Hash()
+		require.False(t, tr.Delete([]byte{1, 2, 3}))
+		require.False(t, tr.Delete([]byte{1, 2, 3, 4, 5}))
+		require.Equal(t, hash, tr.Hash())
+	})
+
+	t.Run("should get nothing after the key was deleted", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		tr.Put([]byte{1, 2}, []byte("world"))
+
+		require.True(t, tr.Delete([]byte{1, 2, 3, 4}))
+		_, found := tr.Get([]byte{1, 2, 3, 4})
+		require.False(t, found)
+
+		val, found := tr.Get([]byte{1, 2})
+		require.True(t, found)
+		require.Equal(t, []byte("world"), val)
+	})
+
+	t.Run("should get the empty hash after all keys were deleted", func(t *testing.T) {
+		tr := NewTrie()
+		for _, key := range keys {
+			tr.Put(key, []byte("hello"))
+		}
+		for _, key := range keys {
+			require.True(t, tr.Delete(key))
+		}
+		require.Equal(t, EmptyNodeHash, tr.Hash())
+	})
+
+	t.Run("should merge the branch into a leaf when only one child is left", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3, 4}, []byte("verb"))
+		tr.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("coin"))
+
+		require.True(t, tr.Delete([]byte{1, 2, 3, 4}))
+
+		leaf := NewLeafNodeFromBytes([]byte{1, 2, 3, 4, 5, 6}, []byte("coin"))
+		require.Equal(t, leaf.Hash(), tr.Hash())
+	})
+
+	t.Run("should get the same hash as if the key was never put", func(t *testing.T) {
+		for i := range keys {
+			trie1, trie2 := NewTrie(), NewTrie()
+			for j, key := range keys {
+				value := []byte(fmt.Sprintf("value %v", j))
+				trie1.Put(key, value)
+				if i != j {
+					trie2.Put(key, value)
+				}
+			}
+
+			require.True(t, trie1.Delete(keys[i]))
+			require.Equal(t, trie2.Hash(), trie1.Hash(), "deleted key: %x", keys[i])
+		}
+	})
+
+	t.Run("should get the same hash as go-ethereum's trie after each deletion", func(t *testing.T) {
+		tr := NewTrie()
+		mpt := new(trie.Trie)
+		for j, key := range keys {
+			value := []byte(fmt.Sprintf("value %v", j))
+			tr.Put(key, value)
+			mpt.Update(key, value)
+		}
+		require.Equal(t, mpt.Hash().Bytes(), tr.Hash())
+
+		for _, key := range keys {
+			tr.Delete(key)
+			mpt.Delete(key)
+			require.Equal(t, mpt.Hash().Bytes(), tr.Hash(), "deleted key: %x", key)
+		}
+	})
+}
+
+func TestClearPrefix(t *testing.T) {
+	t.Run("should remove all keys under the prefix", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2}, []byte("a"))
+		tr.Put([]byte{1, 2, 3}, []byte("b"))
+		tr.Put([]byte{1, 2, 4, 5}, []byte("c"))
+		tr.Put([]byte{1, 3}, []byte("d"))
+		tr.Put([]byte{2}, []byte("e"))
+
+		require.Equal(t, 3, tr.ClearPrefix([]byte{1, 2}))
+
+		expected := NewTrie()
+		expected.Put([]byte{1, 3}, []byte("d"))
+		expected.Put([]byte{2}, []byte("e"))
+		require.Equal(t, expected.Hash(), tr.Hash())
+	})
+
+	t.Run("should get the same hash as deleting the keys one by one", func(t *testing.T) {
+		keys := [][]byte{
+			{1, 2},
+			{1, 2, 3},
+			{1, 2, 4, 5},
+			{1, 3},
+			{2},
+		}
+		trie1, trie2 := NewTrie(), NewTrie()
+		for _, key := range keys {
+			trie1.Put(key, []byte("hello"))
+			trie2.Put(key, []byte("hello"))
+		}
+
+		trie1.ClearPrefix([]byte{1, 2})
+		trie2.Delete([]byte{1, 2})
+		trie2.Delete([]byte{1, 2, 3})
+		trie2.Delete([]byte{1, 2, 4, 5})
+		require.Equal(t, trie2.Hash(), trie1.Hash())
+	})
+
+	t.Run("should remove the subtree when the prefix ends within an extension node", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3, 4, 5}, []byte("a"))
+		tr.Put([]byte{1, 2, 3, 4, 6}, []byte("b"))
+		tr.Put([]byte{9}, []byte("c"))
+
+		require.Equal(t, 2, tr.ClearPrefix([]byte{1, 2}))
+
+		leaf := NewLeafNodeFromBytes([]byte{9}, []byte("c"))
+		require.Equal(t, leaf.Hash(), tr.Hash())
+	})
+
+	t.Run("should not change the trie if no key has the prefix", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3, 4, 5}, []byte("a"))
+		tr.Put([]byte{1, 2, 3, 4, 6}, []byte("b"))
+		tr.Put([]byte{9}, []byte("c"))
+		hash := tr.Hash()
+
+		require.Equal(t, 0, tr.ClearPrefix([]byte{1, 2, 5}))
+		require.Equal(t, 0, tr.ClearPrefix([]byte{8}))
+		require.Equal(t, hash, tr.Hash())
+	})
+
+	t.Run("should remove all keys with an empty prefix", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3, 4, 5}, []byte("a"))
+		tr.Put([]byte{1, 2, 3, 4, 6}, []byte("b"))
+		tr.Put([]byte{9}, []byte("c"))
+
+		require.Equal(t, 3, tr.ClearPrefix([]byte{}))
+		require.Equal(t, EmptyNodeHash, tr.Hash())
+	})
+}
+
+func TestEmptyValue(t *testing.T) {
+	t.Run("should delete the key when putting an empty value", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		tr.Put([]byte{1, 2}, []byte("world"))
+		tr.Put([]byte{1, 2}, []byte{})
+
+		_, found := tr.Get([]byte{1, 2})
+		require.False(t, found)
+
+		expected := NewTrie()
+		expected.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		require.Equal(t, expected.Hash(), tr.Hash())
+
+		tr.Put([]byte{1, 2, 3, 4}, nil)
+		require.Equal(t, EmptyNodeHash, tr.Hash())
+	})
+
+	t.Run("should get the same hash as go-ethereum's trie when putting an empty value", func(t *testing.T) {
+		tr := NewTrie()
+		mpt := new(trie.Trie)
+		tr.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		mpt.Update([]byte{1, 2, 3, 4}, []byte("hello"))
+		tr.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+		mpt.Update([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+
+		tr.Put([]byte{1, 2, 3, 4}, []byte{})
+		mpt.Update([]byte{1, 2, 3, 4}, []byte{})
+		require.Equal(t, mpt.Hash().Bytes(), tr.Hash())
+	})
+
+	t.Run("should not store an empty value for a key that is a prefix of another key", func(t *testing.T) {
+		short, long := []byte("ab"), []byte("abcd")
+
+		// the empty value would end up in the BranchNode created when the longer key is put
+		tr := NewTrie()
+		tr.Put(short, []byte{})
+		tr.Put(long, []byte("hello"))
+
+		_, found := tr.Get(short)
+		require.False(t, found)
+
+		expected := NewTrie()
+		expected.Put(long, []byte("hello"))
+		require.Equal(t, expected.Hash(), tr.Hash())
+
+		// Get and the proof must agree on the absence
+		_, found = tr.Prove(short)
+		require.False(t, found)
+		_, proof, found := tr.GetWithProof(short)
+		require.False(t, found)
+		val, err := VerifyProof(tr.Hash(), short, proof)
+		require.NoError(t, err)
+		require.Nil(t, val)
+	})
+
+	t.Run("should delete a key stored in a branch node when putting an empty value", func(t *testing.T) {
+		short, long := []byte("ab"), []byte("abcd")
+
+		tr := NewTrie()
+		tr.Put(long, []byte("hello"))
+		tr.Put(short, []byte("world"))
+		tr.Put(short, []byte{})
+
+		_, found := tr.Get(short)
+		require.False(t, found)
+
+		expected := NewTrie()
+		expected.Put(long, []byte("hello"))
+		require.Equal(t, expected.Hash(), tr.Hash())
+
+		_, proof, found := tr.GetWithProof(short)
+		require.False(t, found)
+		val, err := VerifyProof(tr.Hash(), short, proof)
+		require.NoError(t, err)
+		require.Nil(t, val)
+	})
+}
+
+// keys that are prefixes of other keys are stored as BranchNode values
+func TestPrefixKeys(t *testing.T) {
+	short, long := []byte("ab"), []byte("abcd")
+
+	t.Run("should put a key that ends within an extension node", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		tr.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+		tr.Put([]byte{1, 2}, []byte("trie"))
+
+		val, found := tr.Get([]byte{1, 2})
+		require.True(t, found)
+		require.Equal(t, []byte("trie"), val)
+
+		val, found = tr.Get([]byte{1, 2, 3, 4})
+		require.True(t, found)
+		require.Equal(t, []byte("hello"), val)
+
+		reversed := NewTrie()
+		reversed.Put([]byte{1, 2}, []byte("trie"))
+		reversed.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+		reversed.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		require.Equal(t, reversed.Hash(), tr.Hash())
+
+		mpt := new(trie.Trie)
+		mpt.Update([]byte{1, 2, 3, 4}, []byte("hello"))
+		mpt.Update([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+		mpt.Update([]byte{1, 2}, []byte("trie"))
+		require.Equal(t, mpt.Hash().Bytes(), tr.Hash())
+	})
+
+	t.Run("should get both keys in either order", func(t *testing.T) {
+		trie1, trie2 := NewTrie(), NewTrie()
+		trie1.Put(short, []byte("v1"))
+		trie1.Put(long, []byte("v2"))
+		trie2.Put(long, []byte("v2"))
+		trie2.Put(short, []byte("v1"))
+		require.Equal(t, trie1.Hash(), trie2.Hash())
+
+		for _, tr := range []*Trie{trie1, trie2} {
+			val, found := tr.Get(short)
+			require.True(t, found)
+			require.Equal(t, []byte("v1"), val)
+
+			val, found = tr.Get(long)
+			require.True(t, found)
+			require.Equal(t, []byte("v2"), val)
+		}
+	})
+
+	t.Run("should prove the key stored in a branch node", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put(short, []byte("v1"))
+		tr.Put(long, []byte("v2"))
+
+		proof, found := tr.Prove(short)
+		require.True(t, found)
+
+		val, err := VerifyProof(tr.Hash(), short, proof)
+		require.NoError(t, err)
+		require.Equal(t, []byte("v1"), val)
+	})
+
+	t.Run("should keep the shorter key after the longer key was deleted", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put(short, []byte("v1"))
+		tr.Put(long, []byte("v2"))
+		require.True(t, tr.Delete(long))
+
+		val, found := tr.Get(short)
+		require.True(t, found)
+		require.Equal(t, []byte("v1"), val)
+
+		expected := NewTrie()
+		expected.Put(short, []byte("v1"))
+		require.Equal(t, expected.Hash(), tr.Hash())
+	})
+
+	t.Run("should keep the longer key after the shorter key was deleted", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put(short, []byte("v1"))
+		tr.Put(long, []byte("v2"))
+		require.True(t, tr.Delete(short))
+
+		val, found := tr.Get(long)
+		require.True(t, found)
+		require.Equal(t, []byte("v2"), val)
+
+		expected := NewTrie()
+		expected.Put(long, []byte("v2"))
+		require.Equal(t, expected.Hash(), tr.Hash())
+	})
+}
+
+// the empty key is stored in the root node, either as a LeafNode with an empty path,
+// or as the value of the root BranchNode
+func TestEmptyKey(t *testing.T) {
+	emptyKey := []byte{}
+
+	t.Run("should get and update the value of the empty key", func(t *testing.T) {
+		tr := NewTrie()
+		_, found := tr.Get(emptyKey)
+		require.False(t, found)
+
+		tr.Put(emptyKey, []byte("hello"))
+		val, found := tr.Get(emptyKey)
+		require.True(t, found)
+		require.Equal(t, []byte("hello"), val)
+
+		tr.Put(emptyKey, []byte("world"))
+		val, found = tr.Get(emptyKey)
+		require.True(t, found)
+		require.Equal(t, []byte("world"), val)
+
+		leaf := NewLeafNodeFromNibbles([]Nibble{}, []byte("world"))
+		require.Equal(t, leaf.Hash(), tr.Hash())
+	})
+
+	t.Run("should store the empty key in the root branch node along with other keys", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		tr.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+		tr.Put(emptyKey, []byte("empty"))
+
+		branch, ok := tr.root.(*BranchNode)
+		require.True(t, ok)
+		require.Equal(t, []byte("empty"), branch.Value)
+
+		val, found := tr.Get(emptyKey)
+		require.True(t, found)
+		require.Equal(t, []byte("empty"), val)
+
+		val, found = tr.Get([]byte{1, 2, 3, 4})
+		require.True(t, found)
+		require.Equal(t, []byte("hello"), val)
+
+		mpt := new(trie.Trie)
+		mpt.Update([]byte{1, 2, 3, 4}, []byte("hello"))
+		mpt.Update([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+		mpt.Update(emptyKey, []byte("empty"))
+		require.Equal(t, mpt.Hash().Bytes(), tr.Hash())
+	})
+
+	t.Run("should restore the prior hash after deleting the empty key", func(t *testing.T) {
+		tr := NewTrie()
+		require.False(t, tr.Delete(emptyKey))
+
+		tr.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		tr.Put([]byte{1, 2, 3, 4, 5, 6}, []byte("world"))
+		hash := tr.Hash()
+
+		tr.Put(emptyKey, []byte("empty"))
+		require.NotEqual(t, hash, tr.Hash())
+
+		require.True(t, tr.Delete(emptyKey))
+		_, found := tr.Get(emptyKey)
+		require.False(t, found)
+		require.Equal(t, hash, tr.Hash())
+	})
+
+	t.Run("should prove the empty key", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put(emptyKey, []byte("empty"))
+		tr.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+
+		proof, found := tr.Prove(emptyKey)
+		require.True(t, found)
+
+		val, err := VerifyProof(tr.Hash(), emptyKey, proof)
+		require.NoError(t, err)
+		require.Equal(t, []byte("empty"), val)
+	})
+
+	t.Run("should prove the absence of the empty key", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2, 3, 4}, []byte("hello"))
+		tr.Put([]byte{5, 6, 7, 8}, []byte("world"))
+
+		_, proof, found := tr.GetWithProof(emptyKey)
+		require.False(t, found)
+
+		val, err := VerifyProof(tr.Hash(), emptyKey, proof)
+		require.NoError(t, err)
+		require.Nil(t, val)
+	})
+}
+
+func TestNibblePath(t *testing.T) {
+	t.Run("should get the value put at an odd length path", func(t *testing.T) {
+		tr := NewTrie()
+		require.NoError(t, tr.PutAtPath([]Nibble{0, 1, 0}, []byte("odd")))
+
+		val, found := tr.GetAtPath([]Nibble{0, 1, 0})
+		require.True(t, found)
+		require.Equal(t, []byte("odd"), val)
+
+		_, found = tr.GetAtPath([]Nibble{0, 1})
+		require.False(t, found)
+
+		leaf := NewLeafNodeFromNibbles([]Nibble{0, 1, 0}, []byte("odd"))
+		require.Equal(t, leaf.Hash(), tr.Hash())
+	})
+
+	t.Run("should store an odd length path in the branch node shared with byte keys", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2}, []byte("hello"))
+		tr.Put([]byte{1, 3}, []byte("world"))
+		require.NoError(t, tr.PutAtPath([]Nibble{0, 1, 0}, []byte("odd")))
+
+		ext, ok := tr.root.(*ExtensionNode)
+		require.True(t, ok)
+		require.Equal(t, []Nibble{0, 1, 0}, ext.Path)
+		branch, ok := ext.Next.(*BranchNode)
+		require.True(t, ok)
+		require.Equal(t, []byte("odd"), branch.Value)
+
+		val, found := tr.Get([]byte{1, 2})
+		require.True(t, found)
+		require.Equal(t, []byte("hello"), val)
+
+		val, found = tr.GetAtPath(FromBytes([]byte{1, 3}))
+		require.True(t, found)
+		require.Equal(t, []byte("world"), val)
+
+		require.True(t, tr.DeleteAtPath([]Nibble{0, 1, 0}))
+		_, found = tr.GetAtPath([]Nibble{0, 1, 0})
+		require.False(t, found)
+
+		expected := NewTrie()
+		expected.Put([]byte{1, 2}, []byte("hello"))
+		expected.Put([]byte{1, 3}, []byte("world"))
+		require.Equal(t, expected.Hash(), tr.Hash())
+	})
+
+	t.Run("should not be affected by changes to the path after put", func(t *testing.T) {
+		tr := NewTrie()
+		path := []Nibble{1, 2, 3}
+		require.NoError(t, tr.PutAtPath(path, []byte("hello")))
+		path[0] = 4
+
+		val, found := tr.GetAtPath([]Nibble{1, 2, 3})
+		require.True(t, found)
+		require.Equal(t, []byte("hello"), val)
+	})
+
+	t.Run("should reject a path containing a nibble not less than 16", func(t *testing.T) {
+		tr := NewTrie()
+		tr.Put([]byte{1, 2}, []byte("hello"))
+		tr.Put([]byte{1, 3}, []byte("world"))
+		hash := tr.Hash()
+
+		// 16 would index past the 16 children of the branch node at 0, 1, 0
+		invalid := []Nibble{0, 1, 0, 16}
+
+		_, found := tr.GetAtPath(invalid)
+		require.False(t, found)
+
+		require.False(t, tr.DeleteAtPath(invalid))
+
+		require.Error(t, tr.PutAtPath(invalid, []byte("bad")))
+		require.Error(t, tr.PutAtPath(invalid, nil))
+		require.Equal(t, hash, tr.Hash())
+	})
+}